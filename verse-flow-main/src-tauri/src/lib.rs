@@ -1,21 +1,65 @@
 use std::fs;
-use std::sync::{Arc, Mutex};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use tauri::{Manager, RunEvent};
-use tauri_plugin_shell::process::{CommandChild, CommandEvent};
-use tauri_plugin_shell::ShellExt;
 
-const BACKEND_PORT: &str = "3001";
+mod log_file;
+mod supervisor;
+
+use log_file::BackendLog;
+use supervisor::SupervisorHandle;
+
 const BACKEND_SIDECAR_NAME: &str = "verse-backend";
 
+fn pick_backend_port() -> std::io::Result<u16> {
+  let listener = TcpListener::bind("127.0.0.1:0")?;
+  listener.local_addr().map(|addr| addr.port())
+}
+
+#[tauri::command]
+fn backend_base_url(state: tauri::State<SupervisorHandle>) -> String {
+  format!("http://127.0.0.1:{}", state.port())
+}
+
+#[tauri::command]
+fn backend_status(state: tauri::State<SupervisorHandle>) -> supervisor::BackendStatus {
+  state.status()
+}
+
+#[tauri::command]
+fn restart_backend(state: tauri::State<SupervisorHandle>) {
+  state.restart();
+}
+
+#[tauri::command]
+fn stop_backend(state: tauri::State<SupervisorHandle>) {
+  state.stop();
+}
+
+#[tauri::command]
+fn start_backend(state: tauri::State<SupervisorHandle>) {
+  state.start();
+}
+
+#[tauri::command]
+fn backend_log_dir(state: tauri::State<Arc<BackendLog>>) -> String {
+  state.logs_dir().to_string_lossy().to_string()
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-  let sidecar_child: Arc<Mutex<Option<CommandChild>>> = Arc::new(Mutex::new(None));
-  let sidecar_child_for_setup = Arc::clone(&sidecar_child);
-  let sidecar_child_for_exit = Arc::clone(&sidecar_child);
-
   let app = tauri::Builder::default()
     .plugin(tauri_plugin_shell::init())
+    .invoke_handler(tauri::generate_handler![
+      backend_base_url,
+      backend_status,
+      restart_backend,
+      stop_backend,
+      start_backend,
+      backend_log_dir
+    ])
     .setup(move |app| {
       if cfg!(debug_assertions) {
         app.handle().plugin(
@@ -29,64 +73,49 @@ pub fn run() {
       let projects_dir = app_data_dir.join("projects");
       fs::create_dir_all(&projects_dir)?;
 
-      let sidecar_command = app
-        .shell()
-        .sidecar(BACKEND_SIDECAR_NAME)?
-        .env("PORT", BACKEND_PORT)
-        .env("VERSE_PROJECTS_DIR", projects_dir.to_string_lossy().to_string());
+      let backend_log = Arc::new(BackendLog::open(&app_data_dir)?);
+      app.manage(Arc::clone(&backend_log));
 
-      let (mut receiver, child) = sidecar_command.spawn()?;
-      log::info!("[verse-backend] spawned with pid {}", child.pid());
-
-      if let Ok(mut child_slot) = sidecar_child_for_setup.lock() {
-        *child_slot = Some(child);
-      }
-
-      tauri::async_runtime::spawn(async move {
-        while let Some(event) = receiver.recv().await {
-          match event {
-            CommandEvent::Stdout(line) => {
-              log::info!("[verse-backend] {}", String::from_utf8_lossy(&line));
-            }
-            CommandEvent::Stderr(line) => {
-              log::error!("[verse-backend] {}", String::from_utf8_lossy(&line));
-            }
-            CommandEvent::Error(error) => {
-              log::error!("[verse-backend] {error}");
-            }
-            CommandEvent::Terminated(payload) => {
-              log::info!(
-                "[verse-backend] terminated: code={:?}, signal={:?}",
-                payload.code,
-                payload.signal
-              );
-            }
-            _ => {}
-          }
-        }
-      });
+      let backend_port = pick_backend_port()?;
+      let supervisor_handle = supervisor::spawn_supervised(
+        app.handle().clone(),
+        projects_dir,
+        backend_port,
+        backend_log,
+      );
+      app.manage(supervisor_handle);
 
       Ok(())
     })
     .build(tauri::generate_context!())
     .expect("error while building tauri application");
 
-  app.run(move |_app_handle, event| {
-    let should_stop_sidecar = matches!(
-      &event,
-      RunEvent::ExitRequested { .. }
-        | RunEvent::Exit
-        | RunEvent::WindowEvent {
-          event: tauri::WindowEvent::CloseRequested { .. },
-          ..
-        }
-    );
-
-    if should_stop_sidecar {
-      if let Ok(mut child_slot) = sidecar_child_for_exit.lock() {
-        if let Some(child) = child_slot.take() {
-          let _ = child.kill();
-        }
+  // Guards against the re-entrant `ExitRequested` that `app_handle.exit(0)`
+  // below raises once the graceful stop completes: without it we'd prevent
+  // our own programmatic exit and loop forever.
+  let shutdown_started = Arc::new(AtomicBool::new(false));
+
+  app.run(move |app_handle, event| {
+    if let RunEvent::ExitRequested { api, .. } = event {
+      if shutdown_started.swap(true, Ordering::SeqCst) {
+        // This is our own `exit(0)` re-raising ExitRequested; let it through.
+        return;
+      }
+
+      if let Some(supervisor) = app_handle.try_state::<SupervisorHandle>() {
+        // Hold the exit open until the backend has had a chance to shut down
+        // cleanly; the graceful stop runs on the async runtime instead of
+        // blocking this (the main) thread. Window-close events are
+        // deliberately left untouched here: closing a window isn't always an
+        // app exit (e.g. macOS), and `ExitRequested` is the single owner of
+        // backend shutdown.
+        api.prevent_exit();
+        let supervisor = supervisor.inner().clone();
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+          supervisor.shutdown_gracefully().await;
+          app_handle.exit(0);
+        });
       }
     }
   });
@@ -0,0 +1,102 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const LOG_FILE_NAME: &str = "verse-backend.log";
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_LOG_FILES: u32 = 5;
+
+/// Rotating on-disk log for the backend sidecar's stdout/stderr and
+/// lifecycle events, so release builds keep diagnostics even though the
+/// console sink (`tauri_plugin_log`) is debug-only.
+pub struct BackendLog {
+  dir: PathBuf,
+  state: Mutex<(File, u64)>,
+}
+
+impl BackendLog {
+  pub fn open(app_data_dir: &Path) -> std::io::Result<Self> {
+    let dir = app_data_dir.join("logs");
+    fs::create_dir_all(&dir)?;
+    let (file, size) = open_current(&dir)?;
+    Ok(Self {
+      dir,
+      state: Mutex::new((file, size)),
+    })
+  }
+
+  /// Directory the rotating log files live in, so the frontend can offer a
+  /// "reveal logs" menu item.
+  pub fn logs_dir(&self) -> &Path {
+    &self.dir
+  }
+
+  pub fn append(&self, line: &str) {
+    let mut guard = match self.state.lock() {
+      Ok(guard) => guard,
+      Err(_) => return,
+    };
+
+    let entry = format!("{} {}\n", timestamp(), line);
+
+    if guard.1 + entry.len() as u64 > MAX_LOG_FILE_BYTES {
+      if let Err(error) = rotate(&self.dir) {
+        log::error!("[verse-backend] failed to rotate log files: {error}");
+      }
+      match open_current(&self.dir) {
+        Ok(reopened) => *guard = reopened,
+        Err(error) => {
+          log::error!("[verse-backend] failed to reopen log file: {error}");
+          return;
+        }
+      }
+    }
+
+    if let Err(error) = guard.0.write_all(entry.as_bytes()) {
+      log::error!("[verse-backend] failed to write to log file: {error}");
+      return;
+    }
+    guard.1 += entry.len() as u64;
+  }
+}
+
+fn open_current(dir: &Path) -> std::io::Result<(File, u64)> {
+  let path = dir.join(LOG_FILE_NAME);
+  let file = OpenOptions::new().create(true).append(true).open(&path)?;
+  let size = file.metadata()?.len();
+  Ok((file, size))
+}
+
+/// Shifts `verse-backend.log.N` -> `.N+1` (dropping anything past
+/// `MAX_LOG_FILES`) and `verse-backend.log` -> `.1`, freeing up the base
+/// name for a fresh file.
+fn rotate(dir: &Path) -> std::io::Result<()> {
+  let oldest = dir.join(format!("{LOG_FILE_NAME}.{MAX_LOG_FILES}"));
+  if oldest.exists() {
+    fs::remove_file(&oldest)?;
+  }
+
+  for index in (1..MAX_LOG_FILES).rev() {
+    let from = dir.join(format!("{LOG_FILE_NAME}.{index}"));
+    let to = dir.join(format!("{LOG_FILE_NAME}.{}", index + 1));
+    if from.exists() {
+      fs::rename(&from, &to)?;
+    }
+  }
+
+  let current = dir.join(LOG_FILE_NAME);
+  if current.exists() {
+    fs::rename(&current, dir.join(format!("{LOG_FILE_NAME}.1")))?;
+  }
+
+  Ok(())
+}
+
+fn timestamp() -> String {
+  let now = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap_or_default();
+  format!("[{}.{:03}]", now.as_secs(), now.subsec_millis())
+}
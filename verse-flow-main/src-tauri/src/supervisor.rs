@@ -0,0 +1,335 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+
+use crate::log_file::BackendLog;
+use crate::BACKEND_SIDECAR_NAME;
+
+/// Event emitted to the webview on spawn/stderr/error/termination transitions.
+const BACKEND_STATUS_EVENT: &str = "backend://status";
+/// Event emitted once the backend's port starts accepting connections.
+const BACKEND_READY_EVENT: &str = "backend://ready";
+/// Event emitted if the readiness probe times out without the backend ever
+/// becoming reachable, so the UI can distinguish "never came up" from a
+/// transient error mid-session.
+const BACKEND_STARTUP_FAILED_EVENT: &str = "backend://startup-failed";
+
+/// Shared handle to the currently-running sidecar child, if any.
+type SharedChild = Arc<Mutex<Option<CommandChild>>>;
+
+const RESTART_BASE_DELAY_MS: u64 = 500;
+const RESTART_MAX_DELAY_MS: u64 = 30_000;
+const RESTART_BACKOFF_RESET_SECS: u64 = 30;
+
+const READINESS_POLL_INTERVAL_MS: u64 = 100;
+const READINESS_TIMEOUT_SECS: u64 = 15;
+
+/// How often the supervisor loop checks whether it's been re-enabled after
+/// `stop_backend` was called.
+const ENABLED_POLL_INTERVAL_MS: u64 = 200;
+
+/// How long to wait for the backend to exit on its own after a graceful
+/// shutdown request before falling back to a hard kill.
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Snapshot of the backend sidecar's state, returned by the `backend_status`
+/// command.
+#[derive(Serialize)]
+pub struct BackendStatus {
+  pub running: bool,
+  pub pid: Option<u32>,
+  pub port: u16,
+  pub restart_count: u32,
+}
+
+/// Tauri-managed handle to the supervised backend sidecar. Cloning is cheap
+/// (it's a bundle of `Arc`s) and every clone controls the same sidecar.
+#[derive(Clone)]
+pub struct SupervisorHandle {
+  child: SharedChild,
+  port: u16,
+  restart_count: Arc<AtomicU32>,
+  enabled: Arc<AtomicBool>,
+}
+
+impl SupervisorHandle {
+  pub fn port(&self) -> u16 {
+    self.port
+  }
+
+  pub fn status(&self) -> BackendStatus {
+    let (running, pid) = match self.child.lock() {
+      Ok(slot) => (slot.is_some(), slot.as_ref().map(|child| child.pid())),
+      Err(_) => (false, None),
+    };
+
+    BackendStatus {
+      running,
+      pid,
+      port: self.port,
+      restart_count: self.restart_count.load(Ordering::SeqCst),
+    }
+  }
+
+  /// Kills the current sidecar process; the supervisor loop notices the
+  /// termination and, if still enabled, respawns it.
+  pub fn restart(&self) {
+    if let Ok(mut slot) = self.child.lock() {
+      if let Some(child) = slot.take() {
+        let _ = child.kill();
+      }
+    }
+  }
+
+  /// Kills the current sidecar and stops the supervisor from respawning it
+  /// until `start()` is called again.
+  pub fn stop(&self) {
+    self.enabled.store(false, Ordering::SeqCst);
+    self.restart();
+  }
+
+  /// Re-enables the supervisor loop, which will spawn the sidecar again if
+  /// it isn't already running.
+  pub fn start(&self) {
+    self.enabled.store(true, Ordering::SeqCst);
+  }
+
+  /// Asks the backend to shut down cleanly (`POST /shutdown`), waits up to
+  /// `SHUTDOWN_GRACE_PERIOD` for it to exit on its own, and only falls back
+  /// to killing it if it hasn't. Disables the supervisor so it won't respawn.
+  ///
+  /// Runs entirely on the async runtime so callers driving this from the
+  /// Tauri event loop (e.g. `RunEvent::ExitRequested`) should call
+  /// `api.prevent_exit()` first and `.await` this off the main thread rather
+  /// than blocking it.
+  pub async fn shutdown_gracefully(&self) {
+    self.enabled.store(false, Ordering::SeqCst);
+
+    let running = matches!(self.child.lock(), Ok(slot) if slot.is_some());
+    if !running {
+      return;
+    }
+
+    if let Err(error) = request_shutdown(self.port).await {
+      log::warn!("[verse-backend] failed to request graceful shutdown: {error}");
+    }
+
+    let deadline = Instant::now() + SHUTDOWN_GRACE_PERIOD;
+    while Instant::now() < deadline {
+      let exited = matches!(self.child.lock(), Ok(slot) if slot.is_none());
+      if exited {
+        return;
+      }
+      tokio::time::sleep(SHUTDOWN_POLL_INTERVAL).await;
+    }
+
+    log::warn!("[verse-backend] did not exit within the grace period, killing");
+    self.restart();
+  }
+}
+
+/// Sends a minimal `POST /shutdown` request over a raw TCP connection,
+/// avoiding the need for an HTTP client dependency just for this one call.
+async fn request_shutdown(port: u16) -> std::io::Result<()> {
+  use tokio::io::AsyncWriteExt;
+
+  let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port)).await?;
+  let request = format!(
+    "POST /shutdown HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+  );
+  stream.write_all(request.as_bytes()).await
+}
+
+/// Spawns the verse-backend sidecar and supervises it for the lifetime of the
+/// app: if it exits (crash, signal, or otherwise) while the app is still
+/// running, it is respawned with exponential backoff. The backoff resets to
+/// its base delay once a spawned process survives `RESTART_BACKOFF_RESET_SECS`.
+/// Returns a `SupervisorHandle` for inspecting and controlling the sidecar.
+pub fn spawn_supervised(
+  app: AppHandle,
+  projects_dir: PathBuf,
+  port: u16,
+  backend_log: Arc<BackendLog>,
+) -> SupervisorHandle {
+  let handle = SupervisorHandle {
+    child: Arc::new(Mutex::new(None)),
+    port,
+    restart_count: Arc::new(AtomicU32::new(0)),
+    enabled: Arc::new(AtomicBool::new(true)),
+  };
+
+  let child_slot = Arc::clone(&handle.child);
+  let restart_count = Arc::clone(&handle.restart_count);
+  let enabled = Arc::clone(&handle.enabled);
+
+  tauri::async_runtime::spawn(async move {
+    let mut delay_ms = RESTART_BASE_DELAY_MS;
+    let mut is_first_spawn = true;
+
+    loop {
+      if !enabled.load(Ordering::SeqCst) {
+        tokio::time::sleep(Duration::from_millis(ENABLED_POLL_INTERVAL_MS)).await;
+        continue;
+      }
+
+      let sidecar_command = match app.shell().sidecar(BACKEND_SIDECAR_NAME) {
+        Ok(cmd) => cmd
+          .env("PORT", port.to_string())
+          .env("VERSE_PROJECTS_DIR", projects_dir.to_string_lossy().to_string()),
+        Err(error) => {
+          log::error!("[verse-backend] failed to prepare sidecar command: {error}");
+          sleep_and_back_off(&mut delay_ms).await;
+          continue;
+        }
+      };
+
+      let (mut receiver, child) = match sidecar_command.spawn() {
+        Ok(pair) => pair,
+        Err(error) => {
+          log::error!("[verse-backend] failed to spawn sidecar: {error}");
+          sleep_and_back_off(&mut delay_ms).await;
+          continue;
+        }
+      };
+
+      if !is_first_spawn {
+        restart_count.fetch_add(1, Ordering::SeqCst);
+      }
+      is_first_spawn = false;
+
+      log::info!("[verse-backend] spawned with pid {}", child.pid());
+      let pid = child.pid();
+      backend_log.append(&format!("spawned with pid {pid}"));
+      if let Ok(mut slot) = child_slot.lock() {
+        *slot = Some(child);
+      }
+      emit_status(
+        &app,
+        json!({ "state": "spawned", "pid": pid, "restart_count": restart_count.load(Ordering::SeqCst) }),
+      );
+      spawn_readiness_probe(app.clone(), port);
+
+      let started_at = Instant::now();
+
+      while let Some(event) = receiver.recv().await {
+        match event {
+          CommandEvent::Stdout(line) => {
+            let message = String::from_utf8_lossy(&line).to_string();
+            log::info!("[verse-backend] {message}");
+            backend_log.append(&format!("stdout: {message}"));
+          }
+          CommandEvent::Stderr(line) => {
+            let message = String::from_utf8_lossy(&line).to_string();
+            log::error!("[verse-backend] {message}");
+            backend_log.append(&format!("stderr: {message}"));
+            emit_status(&app, json!({ "state": "error", "message": message }));
+          }
+          CommandEvent::Error(error) => {
+            log::error!("[verse-backend] {error}");
+            backend_log.append(&format!("error: {error}"));
+            emit_status(&app, json!({ "state": "error", "message": error }));
+          }
+          CommandEvent::Terminated(payload) => {
+            log::info!(
+              "[verse-backend] terminated: code={:?}, signal={:?}",
+              payload.code,
+              payload.signal
+            );
+            backend_log.append(&format!(
+              "terminated: code={:?}, signal={:?}",
+              payload.code, payload.signal
+            ));
+            emit_status(
+              &app,
+              json!({
+                "state": "terminated",
+                "code": payload.code,
+                "signal": payload.signal,
+                "restart_count": restart_count.load(Ordering::SeqCst),
+              }),
+            );
+          }
+          _ => {}
+        }
+      }
+
+      if let Ok(mut slot) = child_slot.lock() {
+        slot.take();
+      }
+
+      if started_at.elapsed() >= Duration::from_secs(RESTART_BACKOFF_RESET_SECS) {
+        delay_ms = RESTART_BASE_DELAY_MS;
+      }
+
+      if !enabled.load(Ordering::SeqCst) {
+        log::info!("[verse-backend] stopped, not restarting");
+        backend_log.append("stopped, not restarting");
+        continue;
+      }
+
+      log::warn!("[verse-backend] exited unexpectedly, restarting in {delay_ms}ms");
+      backend_log.append(&format!("exited unexpectedly, restarting in {delay_ms}ms"));
+      sleep_and_back_off(&mut delay_ms).await;
+    }
+  });
+
+  handle
+}
+
+/// Polls the backend's HTTP port until it accepts connections (or the probe
+/// times out) and emits a dedicated `backend://ready` event once the
+/// frontend is safe to talk to it, or `backend://startup-failed` if it never
+/// comes up.
+fn spawn_readiness_probe(app: AppHandle, port: u16) {
+  tauri::async_runtime::spawn(async move {
+    let deadline = Instant::now() + Duration::from_secs(READINESS_TIMEOUT_SECS);
+
+    loop {
+      if tokio::net::TcpStream::connect(format!("127.0.0.1:{port}"))
+        .await
+        .is_ok()
+      {
+        log::info!("[verse-backend] ready, accepting connections on port {port}");
+        emit(&app, BACKEND_READY_EVENT, json!({ "port": port }));
+        return;
+      }
+
+      if Instant::now() >= deadline {
+        log::error!(
+          "[verse-backend] readiness probe timed out after {READINESS_TIMEOUT_SECS}s"
+        );
+        emit(
+          &app,
+          BACKEND_STARTUP_FAILED_EVENT,
+          json!({ "message": "backend did not become ready in time" }),
+        );
+        return;
+      }
+
+      tokio::time::sleep(Duration::from_millis(READINESS_POLL_INTERVAL_MS)).await;
+    }
+  });
+}
+
+fn emit_status(app: &AppHandle, payload: serde_json::Value) {
+  emit(app, BACKEND_STATUS_EVENT, payload);
+}
+
+fn emit(app: &AppHandle, event: &str, payload: serde_json::Value) {
+  if let Err(error) = app.emit(event, payload) {
+    log::error!("[verse-backend] failed to emit {event}: {error}");
+  }
+}
+
+async fn sleep_and_back_off(delay_ms: &mut u64) {
+  tokio::time::sleep(Duration::from_millis(*delay_ms)).await;
+  *delay_ms = (*delay_ms * 2).min(RESTART_MAX_DELAY_MS);
+}